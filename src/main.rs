@@ -1,12 +1,14 @@
-use std::collections::HashMap;
+use std::io::{IsTerminal, Read, Write};
 use std::path::Path;
 use std::process::exit;
 use std::str::FromStr;
 
 use clap::Parser;
 use colored::Colorize;
+use futures_util::StreamExt;
 use mime::Mime;
-use reqwest::{header, Client, Response};
+use reqwest::{header, Client, Method, Response};
+use serde_json::{Map, Value};
 
 #[derive(Parser)]
 #[clap(name = "httpie", version = "0.1.0", about = "A CLI HTTP client")]
@@ -16,12 +18,56 @@ struct Opts {
     subcmd: SubCommand,
     #[clap(short, long, default_value = "0")]
     code: u16,
+    /// Send the body as application/x-www-form-urlencoded instead of JSON.
+    #[clap(long)]
+    form: bool,
+    /// Send the body as CBOR (application/cbor) instead of JSON.
+    #[clap(long)]
+    cbor: bool,
+    /// Extra request headers in `Name: value` form; repeatable.
+    #[clap(short = 'H', long = "header", parse(try_from_str = parse_header))]
+    headers: Vec<Header>,
+    /// Stream the response body to a file instead of pretty-printing it.
+    #[clap(short, long)]
+    download: bool,
+    /// Output file for `--download` (defaults to the server-suggested name).
+    #[clap(short, long)]
+    output: Option<String>,
+    /// Do not follow redirects.
+    #[clap(short = 'n', long)]
+    no_follow: bool,
+    /// Maximum number of redirects to follow.
+    #[clap(long, default_value = "10")]
+    max_redirects: usize,
+    /// Print the outgoing request and any redirect hops before the response.
+    #[clap(short, long)]
+    verbose: bool,
+    /// Emit the response body as-is, disabling pretty-printing and color.
+    #[clap(short, long)]
+    raw: bool,
+    /// Add an extra root certificate (PEM) to the trust store.
+    #[clap(long)]
+    cacert: Option<String>,
+    /// Client certificate (PEM) to present for mutual TLS.
+    #[clap(long)]
+    cert: Option<String>,
+    /// Private key (PEM) for the `--cert` client certificate.
+    #[clap(long)]
+    key: Option<String>,
+    /// Skip TLS certificate verification.
+    #[clap(short = 'k', long)]
+    insecure: bool,
 }
 
 #[derive(Parser)]
 enum SubCommand {
     Get(Get),
-    Post(Post),
+    Post(Request),
+    Put(Request),
+    Patch(Request),
+    Delete(Request),
+    Head(Request),
+    Options(Request),
 }
 
 #[derive(Parser)]
@@ -32,6 +78,15 @@ struct Get {
     file: String,
 }
 
+/// A method invocation carrying a URL and HTTPie-style request items. The body
+/// may also come from stdin (a literal `-` item or piped input).
+#[derive(Parser)]
+struct Request {
+    #[clap(parse(try_from_str = parse_url))]
+    url: String,
+    body: Vec<String>,
+}
+
 fn verify_file(path: &str) -> Result<String, String> {
     if path == "-" || Path::new(path).exists() {
         Ok(path.to_string())
@@ -40,6 +95,66 @@ fn verify_file(path: &str) -> Result<String, String> {
     }
 }
 
+/// Which encoding to use for the request body.
+#[derive(Clone, Copy)]
+enum BodyMode {
+    Json,
+    Form,
+    Cbor,
+}
+
+/// How the response should be rendered, threaded from `Opts` into `print_resp`.
+struct PrintOpts {
+    code: u16,
+    download: bool,
+    output: Option<String>,
+    verbose: bool,
+    raw: bool,
+}
+
+/// Build the redirect policy from the `--no-follow`/`--max-redirects` flags,
+/// logging each hop's `Location` to stderr when verbose.
+fn redirect_policy(no_follow: bool, max: usize, verbose: bool) -> reqwest::redirect::Policy {
+    use reqwest::redirect::Policy;
+    if no_follow {
+        return Policy::none();
+    }
+    Policy::custom(move |attempt| {
+        // `previous()` already includes the URL being redirected from, so it
+        // holds N entries while deciding whether to follow the Nth redirect.
+        if attempt.previous().len() > max {
+            attempt.stop()
+        } else {
+            if verbose {
+                eprintln!("{}", format!("> Location: {}", attempt.url()).yellow());
+            }
+            attempt.follow()
+        }
+    })
+}
+
+/// Merge the client-wide default headers with a built request's own headers
+/// (the latter winning) to reconstruct what actually goes on the wire.
+fn merged_headers(base: &header::HeaderMap, req: &header::HeaderMap) -> header::HeaderMap {
+    let mut merged = base.clone();
+    for (name, value) in req.iter() {
+        merged.insert(name.clone(), value.clone());
+    }
+    merged
+}
+
+/// Echo the outgoing request line and headers to stderr (verbose mode).
+fn print_request(method: &Method, url: &str, headers: &header::HeaderMap) {
+    eprintln!("{}", format!("> {} {}", method, url).yellow());
+    for (name, value) in headers.iter() {
+        eprintln!(
+            "{}",
+            format!("> {}: {}", name, value.to_str().unwrap_or("")).yellow()
+        );
+    }
+    eprintln!();
+}
+
 fn parse_url(s: &str) -> Result<String, String> {
     if s.starts_with("http://") || s.starts_with("https://") {
         Ok(s.to_string())
@@ -48,40 +163,62 @@ fn parse_url(s: &str) -> Result<String, String> {
     }
 }
 
-#[derive(Parser)]
-struct Post {
-    #[clap(parse(try_from_str = parse_url))]
-    url: String,
-    #[clap(parse(try_from_str = parse_key_value))]
-    body: Vec<KeyValue>,
+/// A `Name: value` header parsed from the global `-H`/`--header` option.
+#[derive(Debug, Clone)]
+struct Header {
+    name: header::HeaderName,
+    value: header::HeaderValue,
+}
+
+fn parse_header(s: &str) -> Result<Header, String> {
+    let (name, value) = s
+        .split_once(':')
+        .ok_or_else(|| "header must be in the format `Name: value`".to_string())?;
+    let name = header::HeaderName::from_str(name.trim()).map_err(|e| e.to_string())?;
+    let value = header::HeaderValue::from_str(value.trim()).map_err(|e| e.to_string())?;
+    Ok(Header { name, value })
 }
 
-#[derive(Debug)]
-struct KeyValue {
-    key: String,
-    value: String,
+/// A single positional item in the HTTPie-style grammar. The separator found
+/// while scanning the argument decides how the pair is applied to the request.
+#[derive(Debug, Clone)]
+enum RequestItem {
+    /// `name:value` — an HTTP request header.
+    Header(String, String),
+    /// `name==value` — a URL query parameter.
+    Query(String, String),
+    /// `name=value` — a JSON string field.
+    JsonField(String, String),
+    /// `name:=value` — a raw JSON value (number, bool, array, object, ...).
+    RawJsonField(String, Value),
 }
 
-impl FromStr for KeyValue {
+impl FromStr for RequestItem {
     type Err = anyhow::Error;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parts: Vec<&str> = s.split('=').collect();
-        if parts.len() != 2 || parts[0].is_empty() || parts[1].is_empty() {
-            return Err(anyhow::anyhow!(
-                "Key value pair must be in the format key=value"
-            ));
+        // Scan left-to-right for the first separator, matching the longest
+        // token at each position so `:=`/`==` win over `:`/`=`.
+        for (i, _) in s.char_indices() {
+            let rest = &s[i..];
+            let key = s[..i].to_string();
+            if let Some(value) = rest.strip_prefix(":=") {
+                let value = serde_json::from_str(value)
+                    .map_err(|e| anyhow::anyhow!("invalid JSON value for `{}`: {}", key, e))?;
+                return Ok(RequestItem::RawJsonField(key, value));
+            }
+            if let Some(value) = rest.strip_prefix("==") {
+                return Ok(RequestItem::Query(key, value.to_string()));
+            }
+            if let Some(value) = rest.strip_prefix(':') {
+                return Ok(RequestItem::Header(key, value.to_string()));
+            }
+            if let Some(value) = rest.strip_prefix('=') {
+                return Ok(RequestItem::JsonField(key, value.to_string()));
+            }
         }
-        Ok(KeyValue {
-            key: parts[0].to_string(),
-            value: parts[1].to_string(),
-        })
-    }
-}
-
-fn parse_key_value(s: &str) -> Result<KeyValue, String> {
-    match KeyValue::from_str(s) {
-        Ok(kv) => Ok(kv),
-        Err(e) => Err(e.to_string()),
+        Err(anyhow::anyhow!(
+            "request item must contain one of `:`, `==`, `=` or `:=`"
+        ))
     }
 }
 
@@ -95,22 +232,103 @@ async fn main() -> Result<(), anyhow::Error> {
         header::USER_AGENT,
         header::HeaderValue::from_static("rust-client"),
     );
+    // User-supplied `-H` headers override the hardcoded defaults above.
+    for h in &opts.headers {
+        headers.insert(h.name.clone(), h.value.clone());
+    }
+    // `--raw` turns off all coloring: status line, headers and verbose trace.
+    if opts.raw {
+        colored::control::set_override(false);
+    }
     env_logger::init();
-    let client = Client::builder()
+    // Keep a copy of the client-wide defaults so verbose mode can show the
+    // full header set that ends up on the wire.
+    let base_headers = headers.clone();
+    let mut builder = Client::builder()
         .no_proxy()
         .default_headers(headers)
-        .build()?;
+        .redirect(redirect_policy(
+            opts.no_follow,
+            opts.max_redirects,
+            opts.verbose,
+        ));
+    if let Some(path) = &opts.cacert {
+        let pem = std::fs::read(path)?;
+        builder = builder.add_root_certificate(reqwest::Certificate::from_pem(&pem)?);
+    }
+    match (&opts.cert, &opts.key) {
+        (Some(cert), Some(key)) => {
+            // reqwest wants the certificate and key concatenated in a single PEM.
+            let mut pem = std::fs::read(cert)?;
+            pem.extend_from_slice(&std::fs::read(key)?);
+            builder = builder.identity(reqwest::Identity::from_pem(&pem)?);
+        }
+        (None, None) => {}
+        _ => {
+            return Err(anyhow::anyhow!(
+                "--cert and --key must be provided together"
+            ))
+        }
+    }
+    if opts.insecure {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    let client = builder.build()?;
+    let print = PrintOpts {
+        code: opts.code,
+        download: opts.download,
+        output: opts.output,
+        verbose: opts.verbose,
+        raw: opts.raw,
+    };
+    let mode = if opts.cbor {
+        BodyMode::Cbor
+    } else if opts.form {
+        BodyMode::Form
+    } else {
+        BodyMode::Json
+    };
     let result = match opts.subcmd {
-        SubCommand::Get(ref args) => get(client, args, opts.code).await?,
-        SubCommand::Post(ref args) => post(client, args, opts.code).await?,
+        SubCommand::Get(ref args) => get(client, args, &print, &base_headers).await?,
+        SubCommand::Post(ref args) => {
+            request(client, Method::POST, args, &print, mode, &base_headers).await?
+        }
+        SubCommand::Put(ref args) => {
+            request(client, Method::PUT, args, &print, mode, &base_headers).await?
+        }
+        SubCommand::Patch(ref args) => {
+            request(client, Method::PATCH, args, &print, mode, &base_headers).await?
+        }
+        SubCommand::Delete(ref args) => {
+            request(client, Method::DELETE, args, &print, mode, &base_headers).await?
+        }
+        SubCommand::Head(ref args) => {
+            request(client, Method::HEAD, args, &print, mode, &base_headers).await?
+        }
+        SubCommand::Options(ref args) => {
+            request(client, Method::OPTIONS, args, &print, mode, &base_headers).await?
+        }
     };
     Ok(result)
 }
 #[allow(clippy::needless_question_mark)]
-async fn get(client: Client, args: &Get, code: u16) -> Result<(), anyhow::Error> {
+async fn get(
+    client: Client,
+    args: &Get,
+    print: &PrintOpts,
+    base_headers: &header::HeaderMap,
+) -> Result<(), anyhow::Error> {
     if args.file == "-" {
-        let res = client.get(&args.url).send().await?;
-        return Ok(print_resp(res, code).await?);
+        let req = client.get(&args.url).build()?;
+        if print.verbose {
+            print_request(
+                req.method(),
+                req.url().as_str(),
+                &merged_headers(base_headers, req.headers()),
+            );
+        }
+        let res = client.execute(req).await?;
+        return Ok(print_resp(res, print).await?);
     }
     let mut urls = Vec::new();
     let file = std::fs::read_to_string(&args.file)?;
@@ -130,19 +348,83 @@ async fn get(client: Client, args: &Get, code: u16) -> Result<(), anyhow::Error>
         urls.push(url);
     }
     for url in urls {
-        let res = client.get(url).send().await?;
-        print_resp(res, code).await?;
+        let req = client.get(url).build()?;
+        if print.verbose {
+            print_request(
+                req.method(),
+                req.url().as_str(),
+                &merged_headers(base_headers, req.headers()),
+            );
+        }
+        let res = client.execute(req).await?;
+        print_resp(res, print).await?;
     }
     Ok(())
 }
 #[allow(clippy::needless_question_mark)]
-async fn post(client: Client, args: &Post, code: u16) -> Result<(), anyhow::Error> {
-    let mut body = HashMap::new();
-    for kv in args.body.iter() {
-        body.insert(&kv.key, &kv.value);
+async fn request(
+    client: Client,
+    method: Method,
+    args: &Request,
+    print: &PrintOpts,
+    mode: BodyMode,
+    base_headers: &header::HeaderMap,
+) -> Result<(), anyhow::Error> {
+    let mut headers = header::HeaderMap::new();
+    let mut query: Vec<(String, String)> = Vec::new();
+    let mut body = Map::new();
+    let mut from_stdin = false;
+    for item in args.body.iter() {
+        if item == "-" {
+            from_stdin = true;
+            continue;
+        }
+        match RequestItem::from_str(item)? {
+            RequestItem::Header(name, value) => {
+                headers.insert(
+                    header::HeaderName::from_str(&name)?,
+                    header::HeaderValue::from_str(&value)?,
+                );
+            }
+            RequestItem::Query(name, value) => query.push((name, value)),
+            RequestItem::JsonField(name, value) => {
+                body.insert(name, Value::String(value));
+            }
+            RequestItem::RawJsonField(name, value) => {
+                body.insert(name, value);
+            }
+        }
+    }
+    let mut builder = client.request(method, &args.url).headers(headers).query(&query);
+    // A literal `-` item, or piped (non-TTY) input with no structured body,
+    // sends stdin verbatim using whatever Content-Type the user supplied via
+    // `-H`. Gating the implicit case on a non-TTY stdin means an interactive
+    // session never blocks waiting on a terminal that will not send EOF.
+    if from_stdin || (body.is_empty() && !std::io::stdin().is_terminal()) {
+        let mut buf = String::new();
+        std::io::stdin().read_to_string(&mut buf)?;
+        builder = builder.body(buf);
+    } else {
+        match mode {
+            BodyMode::Json => builder = builder.json(&body),
+            BodyMode::Form => builder = builder.form(&body),
+            BodyMode::Cbor => {
+                builder = builder
+                    .header(header::CONTENT_TYPE, "application/cbor")
+                    .body(serde_cbor::to_vec(&body)?);
+            }
+        }
+    }
+    let req = builder.build()?;
+    if print.verbose {
+        print_request(
+            req.method(),
+            req.url().as_str(),
+            &merged_headers(base_headers, req.headers()),
+        );
     }
-    let res = client.post(&args.url).json(&body).send().await?;
-    Ok(print_resp(res, code).await?)
+    let res = client.execute(req).await?;
+    Ok(print_resp(res, print).await?)
 }
 
 fn print_status(res: &Response) {
@@ -164,7 +446,18 @@ fn print_headers(res: &Response) {
 fn print_body(m: Option<Mime>, body: &String) {
     match m {
         Some(v) if v.type_() == mime::APPLICATION && v.subtype() == mime::JSON => {
-            println!("{}", jsonxf::pretty_print(body).unwrap().cyan());
+            // A `application/json` label is no guarantee the body parses (error
+            // pages lie about their type); fall back to the raw body.
+            match jsonxf::pretty_print(body) {
+                Ok(pretty) => println!("{}", pretty.cyan()),
+                Err(_) => println!("{}", body.cyan()),
+            }
+        }
+        Some(v) if v.subtype() == "xml" => {
+            println!("{}", format_markup(body).cyan());
+        }
+        Some(v) if v.type_() == mime::TEXT && v.subtype() == mime::HTML => {
+            println!("{}", format_markup(body).cyan());
         }
         _ => {
             println!("{}", body.cyan());
@@ -172,8 +465,156 @@ fn print_body(m: Option<Mime>, body: &String) {
     }
 }
 
+/// A markup fragment: either a complete `<...>` tag or a run of text.
+enum Markup {
+    Tag(String),
+    Text(String),
+}
+
+/// Split markup into tags and text runs, treating `<`/`>` as tag delimiters
+/// only outside quoted attribute values so `<input value="a>b">` stays intact.
+fn tokenize_markup(body: &str) -> Vec<Markup> {
+    let mut tokens = Vec::new();
+    let mut rest = body;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('<') {
+            let mut quote: Option<char> = None;
+            let mut end = None;
+            for (i, c) in after.char_indices() {
+                match quote {
+                    Some(q) if c == q => quote = None,
+                    Some(_) => {}
+                    None if c == '"' || c == '\'' => quote = Some(c),
+                    None if c == '>' => {
+                        end = Some(i + c.len_utf8());
+                        break;
+                    }
+                    None => {}
+                }
+            }
+            match end {
+                // `+ 1` accounts for the leading `<` stripped above.
+                Some(e) => {
+                    tokens.push(Markup::Tag(rest[..e + 1].to_string()));
+                    rest = &rest[e + 1..];
+                }
+                None => {
+                    tokens.push(Markup::Text(rest.to_string()));
+                    break;
+                }
+            }
+        } else {
+            let e = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Markup::Text(rest[..e].to_string()));
+            rest = &rest[e..];
+        }
+    }
+    tokens
+}
+
+/// The lowercased element name of a tag, e.g. `<Pre class=x>` -> `pre`.
+fn tag_name(tag: &str) -> Option<String> {
+    let name: String = tag
+        .trim_start_matches('<')
+        .trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect();
+    (!name.is_empty()).then(|| name.to_ascii_lowercase())
+}
+
+/// Indent markup by one level per open tag so XML and HTML responses read as a
+/// nested structure rather than one long line. Splitting happens only on real
+/// tag boundaries, and the contents of `<pre>`/`<textarea>` are emitted
+/// verbatim so preformatted whitespace is preserved.
+fn format_markup(body: &str) -> String {
+    let mut out = String::new();
+    let mut depth: usize = 0;
+    let mut pre_depth: usize = 0;
+    for token in tokenize_markup(body) {
+        match token {
+            Markup::Tag(tag) => {
+                let closing = tag.starts_with("</");
+                let preformatted =
+                    matches!(tag_name(&tag).as_deref(), Some("pre") | Some("textarea"));
+                if pre_depth > 0 {
+                    // Inside preformatted content: reproduce tags verbatim.
+                    out.push_str(&tag);
+                    if preformatted && closing {
+                        pre_depth -= 1;
+                        depth = depth.saturating_sub(1);
+                        out.push('\n');
+                    } else if preformatted {
+                        pre_depth += 1;
+                        depth += 1;
+                    }
+                    continue;
+                }
+                if closing {
+                    depth = depth.saturating_sub(1);
+                }
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                for _ in 0..depth {
+                    out.push_str("  ");
+                }
+                out.push_str(&tag);
+                let opening = !closing
+                    && !tag.starts_with("<!")
+                    && !tag.starts_with("<?")
+                    && !tag.ends_with("/>");
+                if opening {
+                    depth += 1;
+                }
+                // Keep a preformatted block's content on the same run as its
+                // opening tag instead of forcing a newline after it.
+                if opening && preformatted {
+                    pre_depth += 1;
+                } else {
+                    out.push('\n');
+                }
+            }
+            Markup::Text(text) => {
+                if pre_depth > 0 {
+                    out.push_str(&text);
+                    continue;
+                }
+                let trimmed = text.trim();
+                if trimmed.is_empty() {
+                    continue;
+                }
+                if !out.is_empty() && !out.ends_with('\n') {
+                    out.push('\n');
+                }
+                for _ in 0..depth {
+                    out.push_str("  ");
+                }
+                out.push_str(trimmed);
+                out.push('\n');
+            }
+        }
+    }
+    while out.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+/// Decode the raw body to UTF-8, honouring the charset in the `Content-Type`
+/// (via `encoding_rs`) and falling back to UTF-8 when none is declared.
+fn decode_body(m: &Option<Mime>, bytes: &[u8]) -> String {
+    let encoding = m
+        .as_ref()
+        .and_then(|m| m.get_param(mime::CHARSET))
+        .and_then(|c| encoding_rs::Encoding::for_label(c.as_str().as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
+}
+
 //
-async fn print_resp(res: Response, code: u16) -> Result<(), anyhow::Error> {
+async fn print_resp(res: Response, print: &PrintOpts) -> Result<(), anyhow::Error> {
     if res.status().is_client_error() {
         println!(
             "{}",
@@ -190,17 +631,96 @@ async fn print_resp(res: Response, code: u16) -> Result<(), anyhow::Error> {
 
     print_status(&res);
     print_headers(&res);
-    if code != 0 && res.status().as_u16() != code {
+    if print.code != 0 && res.status().as_u16() != print.code {
         exit(1)
     }
+    if print.download {
+        return download(res, print).await;
+    }
     let m = get_content_type(&res);
-    match res.text().await {
-        Ok(body) => print_body(m, &body),
-        Err(e) => println!("Failed to read response body: {}", e),
+    let bytes = res.bytes().await?;
+    if print.raw {
+        std::io::stdout().write_all(&bytes)?;
+        return Ok(());
+    }
+    // CBOR responses are binary; decode them into JSON for human-readable output.
+    if let Some(v) = &m {
+        if v.type_() == mime::APPLICATION && v.subtype() == "cbor" {
+            let value: Value = serde_cbor::from_slice(&bytes)?;
+            let json = serde_json::to_string(&value)?;
+            println!("{}", jsonxf::pretty_print(&json).unwrap().cyan());
+            return Ok(());
+        }
     }
+    let body = decode_body(&m, &bytes);
+    print_body(m, &body);
     Ok(())
 }
 
+/// Stream the response body to disk, reporting progress to stderr.
+async fn download(res: Response, print: &PrintOpts) -> Result<(), anyhow::Error> {
+    let filename = download_filename(&res, print);
+    let mut file = std::fs::File::create(&filename)?;
+    eprintln!("Downloading to {}", filename);
+    let mut downloaded: u64 = 0;
+    let mut stream = res.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        file.write_all(&chunk)?;
+        downloaded += chunk.len() as u64;
+        eprint!("\r{} bytes", downloaded);
+    }
+    eprintln!();
+    Ok(())
+}
+
+/// Pick the download target from `-o`, then `Content-Disposition`, then the
+/// last path segment of the URL, falling back to `index.html`.
+fn download_filename(res: &Response, print: &PrintOpts) -> String {
+    if let Some(output) = &print.output {
+        return output.clone();
+    }
+    if let Some(name) = res
+        .headers()
+        .get(header::CONTENT_DISPOSITION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(content_disposition_filename)
+        .as_deref()
+        .and_then(sanitize_filename)
+    {
+        return name;
+    }
+    res.url()
+        .path_segments()
+        .and_then(|s| s.last())
+        .and_then(sanitize_filename)
+        .unwrap_or_else(|| "index.html".to_string())
+}
+
+/// Extract the `filename=` parameter from a `Content-Disposition` value.
+fn content_disposition_filename(value: &str) -> Option<String> {
+    value.split(';').find_map(|part| {
+        let part = part.trim();
+        part.strip_prefix("filename=")
+            .map(|name| name.trim_matches('"').to_string())
+    })
+}
+
+/// Reduce a server-supplied name to a safe basename so a hostile
+/// `Content-Disposition` (or URL) can't escape the current directory. Returns
+/// `None` when nothing usable remains (empty, `.`/`..`, pure separators).
+fn sanitize_filename(name: &str) -> Option<String> {
+    let base = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or("")
+        .trim();
+    if base.is_empty() || base == "." || base == ".." {
+        return None;
+    }
+    Some(base.to_string())
+}
+
 fn get_content_type(res: &Response) -> Option<Mime> {
     res.headers()
         .get(reqwest::header::CONTENT_TYPE)